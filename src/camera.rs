@@ -0,0 +1,166 @@
+use super::math::Point3;
+
+/// A smooth camera path through a sequence of keyframes.
+///
+/// Each keyframe consists of the camera position and the point the
+/// camera looks at. Positions and targets are interpolated independently
+/// using a uniform __Catmull-Rom__ spline, i.e. the path passes exactly
+/// through every keyframe.
+#[derive(Debug, Clone, Default)]
+pub struct CameraPath {
+    keyframes: Vec<(Point3, Point3)>,
+}
+
+impl CameraPath {
+
+    /// Create a new camera path without any keyframes.
+    pub fn new() -> Self {
+        Self { keyframes: Vec::new() }
+    }
+
+    /// Append a keyframe consisting of the camera position `from` and
+    /// the point `to` the camera looks at.
+    pub fn add_keyframe(&mut self, from: Point3, to: Point3) {
+        self.keyframes.push((from, to));
+    }
+
+    /// Returns the number of keyframes of the given path.
+    pub fn len(&self) -> usize {
+        self.keyframes.len()
+    }
+
+    /// Returns `true` if the given path has no keyframes.
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    /// Sample the camera position and look-at target at `t`.
+    ///
+    /// The keyframes are spread evenly over `[0, 1]`, i.e. keyframe `i`
+    /// of `n` is reached at `t = i / (n - 1)`. Values of `t` outside of
+    /// `[0, 1]` are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::camera::CameraPath;
+    /// use sray::math::Point3;
+    ///
+    /// let mut path = CameraPath::new();
+    /// path.add_keyframe(Point3::new(0.0, 1.0, -5.0), Point3::new(0.0, 0.0, 0.0));
+    /// path.add_keyframe(Point3::new(5.0, 1.0, 0.0), Point3::new(0.0, 0.0, 0.0));
+    /// path.add_keyframe(Point3::new(0.0, 1.0, 5.0), Point3::new(0.0, 0.0, 0.0));
+    ///
+    /// assert_eq!(Point3::new(5.0, 1.0, 0.0), path.sample(0.5).0);
+    /// assert_eq!(Point3::new(0.0, 1.0, 5.0), path.sample(1.5).0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path has no keyframes.
+    pub fn sample(&self, t: f64) -> (Point3, Point3) {
+        assert!(!self.keyframes.is_empty(), "camera path has no keyframes");
+
+        let last = self.keyframes.len() - 1;
+        if last == 0 {
+            return self.keyframes[0];
+        }
+
+        // Find the segment and the position within that segment
+        let s = t.clamp(0.0, 1.0) * last as f64;
+        let i = (s.floor() as usize).min(last - 1);
+        let u = s - i as f64;
+
+        // The first and last keyframes are duplicated to get
+        // control points for the outer segments
+        let k0 = &self.keyframes[i.saturating_sub(1)];
+        let k1 = &self.keyframes[i];
+        let k2 = &self.keyframes[i + 1];
+        let k3 = &self.keyframes[(i + 2).min(last)];
+
+        (
+            catmull_rom(&k0.0, &k1.0, &k2.0, &k3.0, u),
+            catmull_rom(&k0.1, &k1.1, &k2.1, &k3.1, u),
+        )
+    }
+}
+
+/// Interpolate between `p1` and `p2` using a uniform Catmull-Rom
+/// spline with the control points `p0` and `p3`.
+fn catmull_rom(p0: &Point3, p1: &Point3, p2: &Point3, p3: &Point3, u: f64) -> Point3 {
+    let u2 = u * u;
+    let u3 = u2 * u;
+    let spline = |c0: f64, c1: f64, c2: f64, c3: f64| {
+        0.5 * (2.0 * c1 +
+               (-c0 + c2) * u +
+               (2.0 * c0 - 5.0 * c1 + 4.0 * c2 - c3) * u2 +
+               (-c0 + 3.0 * c1 - 3.0 * c2 + c3) * u3)
+    };
+
+    Point3::new(
+        spline(p0.x(), p1.x(), p2.x(), p3.x()),
+        spline(p0.y(), p1.y(), p2.y(), p3.y()),
+        spline(p0.z(), p1.z(), p2.z(), p3.z()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CameraPath;
+    use crate::math::Point3;
+
+    fn path() -> CameraPath {
+        let mut path = CameraPath::new();
+        path.add_keyframe(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 0.0, 1.0));
+        path.add_keyframe(Point3::new(1.0, 2.0, 0.0), Point3::new(1.0, 0.0, 1.0));
+        path.add_keyframe(Point3::new(3.0, 2.0, 1.0), Point3::new(2.0, 0.0, 1.0));
+        path.add_keyframe(Point3::new(4.0, 0.0, 3.0), Point3::new(3.0, 0.0, 1.0));
+        path
+    }
+
+    #[test]
+    fn path_passes_through_each_keyframe() {
+        let path = path();
+
+        for (i, keyframe) in path.keyframes.iter().enumerate() {
+            let t = i as f64 / (path.len() - 1) as f64;
+            assert_eq!(*keyframe, path.sample(t));
+        }
+    }
+
+    #[test]
+    fn sampling_outside_of_the_unit_interval_is_clamped() {
+        let path = path();
+
+        assert_eq!(path.sample(0.0), path.sample(-1.0));
+        assert_eq!(path.sample(1.0), path.sample(2.0));
+    }
+
+    #[test]
+    fn evenly_spaced_collinear_keyframes_give_linear_motion() {
+        let path = path();
+
+        // The look-at targets are evenly spaced along the x axis, so the
+        // inner segment is interpolated linearly.
+        assert_eq!(Point3::new(1.5, 0.0, 1.0), path.sample(0.5).1);
+        assert_eq!(Point3::new(1.25, 0.0, 1.0), path.sample(0.4166666666666667).1);
+    }
+
+    #[test]
+    fn path_is_continuous_across_keyframes() {
+        let path = path();
+        let t = 1.0 / 3.0;
+        let before = path.sample(t - 1e-6).0;
+        let after = path.sample(t + 1e-6).0;
+
+        assert!((after - before).mag() < 1e-4);
+    }
+
+    #[test]
+    fn single_keyframe_path_is_constant() {
+        let mut path = CameraPath::new();
+        path.add_keyframe(Point3::new(1.0, 2.0, 3.0), Point3::new(0.0, 0.0, 0.0));
+
+        assert_eq!((Point3::new(1.0, 2.0, 3.0), Point3::new(0.0, 0.0, 0.0)), path.sample(0.7));
+    }
+}
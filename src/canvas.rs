@@ -1,7 +1,41 @@
+use std::fmt;
+#[cfg(feature = "exr")]
+use std::path::Path;
 use super::color::{Color, DefaultColors};
 
+/// Errors that can occur while writing an OpenEXR file.
+#[cfg(feature = "exr")]
+pub type ExrError = exr::error::Error;
+
+/// Errors that can occur while creating a canvas.
+#[derive(Debug, PartialEq)]
+pub enum CanvasError {
+    /// The number of given pixel values does not match the
+    /// dimensions of the canvas.
+    SizeMismatch { expected: usize, actual: usize },
+    /// The number of pixel values of a canvas with the given
+    /// dimensions can't be represented as `usize`.
+    TooLarge { width: usize, height: usize },
+}
+
+impl fmt::Display for CanvasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CanvasError::SizeMismatch { expected, actual } => {
+                write!(f, "expected {} pixel values but got {}", expected, actual)
+            }
+            CanvasError::TooLarge { width, height } => {
+                write!(f, "a canvas of {}x{} pixels is too large", width, height)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CanvasError {}
+
 pub struct Canvas {
     canvas: Vec<Color>,
+    alpha: Vec<f64>,
     width: usize,
     height: usize,
 }
@@ -10,7 +44,7 @@ impl Canvas {
     
     /// Create a new canvas using the given width and height.
     ///
-    /// Each pixel of the canvas is initialized to black.
+    /// Each pixel of the canvas is initialized to opaque black.
     ///
     /// # Examples
     ///
@@ -25,11 +59,116 @@ impl Canvas {
     pub fn new(width: usize, height: usize) -> Self {
         Self {
             canvas: vec![Color::BLACK; width * height],
+            alpha: vec![1.0; width * height],
             width,
             height
         }
     }
 
+    /// Create a new canvas from an existing pixel buffer.
+    ///
+    /// The pixels are expected in row-major order, i.e. the pixel
+    /// at `(x, y)` is stored at index `x + y * width`.
+    ///
+    /// * Returns `CanvasError::SizeMismatch` if the length of `pixels`
+    ///   is not `width * height`.
+    /// * Returns `CanvasError::TooLarge` if `width * height` overflows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::{Canvas, CanvasError};
+    /// use sray::color::{Color, DefaultColors};
+    ///
+    /// let c = Canvas::from_pixels(2, 1, vec![Color::RED, Color::BLUE]).unwrap();
+    ///
+    /// assert_eq!(&Color::RED, c.pixel_at(0, 0).unwrap());
+    /// assert_eq!(&Color::BLUE, c.pixel_at(1, 0).unwrap());
+    ///
+    /// assert_eq!(
+    ///     Some(CanvasError::SizeMismatch { expected: 4, actual: 2 }),
+    ///     Canvas::from_pixels(2, 2, vec![Color::RED, Color::BLUE]).err()
+    /// );
+    /// ```
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<Color>) -> Result<Self, CanvasError> {
+        let expected = width.checked_mul(height).ok_or(CanvasError::TooLarge { width, height })?;
+
+        if pixels.len() != expected {
+            return Err(CanvasError::SizeMismatch { expected, actual: pixels.len() });
+        }
+
+        Ok(Self {
+            alpha: vec![1.0; pixels.len()],
+            canvas: pixels,
+            width,
+            height
+        })
+    }
+
+    /// Create a new canvas from raw 8 bit RGB values.
+    ///
+    /// Every pixel is represented by three consecutive bytes (red, green
+    /// and blue) in row-major order. Each byte is scaled between 0 and 1.
+    ///
+    /// * Returns `CanvasError::SizeMismatch` if the length of `bytes`
+    ///   is not `width * height * 3`.
+    /// * Returns `CanvasError::TooLarge` if `width * height * 3` overflows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::{Canvas, CanvasError};
+    /// use sray::color::{Color, DefaultColors};
+    ///
+    /// let c = Canvas::from_rgb8(2, 1, &[255, 0, 0, 0, 0, 255]).unwrap();
+    ///
+    /// assert_eq!(&Color::RED, c.pixel_at(0, 0).unwrap());
+    /// assert_eq!(&Color::BLUE, c.pixel_at(1, 0).unwrap());
+    ///
+    /// assert!(Canvas::from_rgb8(2, 1, &[255, 0, 0]).is_err());
+    /// ```
+    pub fn from_rgb8(width: usize, height: usize, bytes: &[u8]) -> Result<Self, CanvasError> {
+        const SCALE: f64 = 255.0;
+
+        let expected = width
+            .checked_mul(height)
+            .and_then(|n| n.checked_mul(3))
+            .ok_or(CanvasError::TooLarge { width, height })?;
+
+        if bytes.len() != expected {
+            return Err(CanvasError::SizeMismatch { expected, actual: bytes.len() });
+        }
+
+        let pixels = bytes
+            .chunks(3)
+            .map(|rgb| Color::new(rgb[0] as f64 / SCALE, rgb[1] as f64 / SCALE, rgb[2] as f64 / SCALE))
+            .collect();
+
+        Self::from_pixels(width, height, pixels)
+    }
+
+    /// Create a new, fully transparent canvas using the given width and height.
+    ///
+    /// Each pixel of the canvas is initialized to black with an alpha of 0.
+    /// Note that [`Canvas::write_pixel`] doesn't change the alpha value, so
+    /// use [`Canvas::write_pixel_rgba`] to make a written pixel visible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    ///
+    /// let c = Canvas::new_transparent(10, 20);
+    ///
+    /// assert_eq!(Some(0.0), c.alpha_at(0, 0));
+    /// ```
+    pub fn new_transparent(width: usize, height: usize) -> Self {
+        Self {
+            alpha: vec![0.0; width * height],
+            ..Self::new(width, height)
+        }
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -41,7 +180,7 @@ impl Canvas {
     /// Returns a reference to the pixel at the position specified by the `x` and `y` coordinates.
     ///
     /// * If the specified indices are in bounds, returns a reference to the color at that
-    /// position, `None` otherwise.
+    ///   position, `None` otherwise.
     ///
     /// The position `(0, 0)` is at the upper left corner of the canvas. `x`
     /// specifies the pixel within a row from left to right and `y` specifies 
@@ -78,7 +217,9 @@ impl Canvas {
     /// Set a color at the position specified by `x` and `y`.
     ///
     /// * The function call won't have an effect if the specified coordinates
-    /// are out of bounds.
+    ///   are out of bounds.
+    /// * The alpha value of the pixel stays unchanged, see
+    ///   [`Canvas::write_pixel_rgba`] to set both.
     ///
     /// Ranges:
     /// * `x` - `[0, width)`
@@ -102,6 +243,124 @@ impl Canvas {
             self.canvas[x + y * self.width] = color;
         }
     }
+
+    /// Set a color and its alpha value at the position specified by `x` and `y`.
+    ///
+    /// * The alpha value is clamped to `[0, 1]`.
+    /// * The function call won't have an effect if the specified coordinates
+    ///   are out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    /// use sray::color::{Color, DefaultColors};
+    ///
+    /// let mut c = Canvas::new_transparent(10, 20);
+    ///
+    /// c.write_pixel_rgba(0, 0, Color::ORANGE, 1.0);
+    /// assert_eq!(&Color::ORANGE, c.pixel_at(0, 0).unwrap());
+    /// assert_eq!(Some(1.0), c.alpha_at(0, 0));
+    /// ```
+    pub fn write_pixel_rgba(&mut self, x: usize, y: usize, color: Color, alpha: f64) {
+        self.write_pixel(x, y, color);
+        self.write_alpha(x, y, alpha);
+    }
+
+    /// Returns the alpha value of the pixel at the position specified by
+    /// the `x` and `y` coordinates.
+    ///
+    /// An alpha of `1` means the pixel is fully opaque, an alpha of `0`
+    /// means it's fully transparent.
+    ///
+    /// * If the specified indices are in bounds, returns the alpha value
+    ///   at that position, `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    ///
+    /// let c = Canvas::new(10, 20);
+    ///
+    /// assert_eq!(Some(1.0), c.alpha_at(0, 0));
+    /// assert_eq!(None, c.alpha_at(10, 0));
+    /// ```
+    pub fn alpha_at(&self, x: usize, y: usize) -> Option<f64> {
+        if x < self.width && y < self.height {
+            Some(self.alpha[x + y * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Set the alpha value at the position specified by `x` and `y`.
+    ///
+    /// * The value is clamped to `[0, 1]`.
+    /// * The function call won't have an effect if the specified coordinates
+    ///   are out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    ///
+    /// let mut c = Canvas::new(10, 20);
+    ///
+    /// c.write_alpha(0, 0, 0.25);
+    /// assert_eq!(Some(0.25), c.alpha_at(0, 0));
+    /// ```
+    pub fn write_alpha(&mut self, x: usize, y: usize, alpha: f64) {
+        if x < self.width && y < self.height {
+            self.alpha[x + y * self.width] = alpha.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Composite `src` over the given canvas with its upper left corner
+    /// placed at `x` and `y`.
+    ///
+    /// The colors are blended using the alpha values of both canvases
+    /// (_over_ operator). Pixels of `src` that fall outside of the given
+    /// canvas are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    /// use sray::color::{Color, DefaultColors};
+    ///
+    /// let mut dst = Canvas::new(2, 2);
+    /// let mut src = Canvas::new_transparent(1, 1);
+    /// src.write_pixel_rgba(0, 0, Color::RED, 0.5);
+    ///
+    /// dst.blit(&src, 1, 1);
+    ///
+    /// assert_eq!(&Color::new(0.5, 0.0, 0.0), dst.pixel_at(1, 1).unwrap());
+    /// assert_eq!(Some(1.0), dst.alpha_at(1, 1));
+    /// assert_eq!(&Color::BLACK, dst.pixel_at(0, 0).unwrap());
+    /// ```
+    pub fn blit(&mut self, src: &Canvas, x: usize, y: usize) {
+        for sy in 0..src.height {
+            for sx in 0..src.width {
+                let (dx, dy) = match (x.checked_add(sx), y.checked_add(sy)) {
+                    (Some(dx), Some(dy)) if dx < self.width && dy < self.height => (dx, dy),
+                    _ => continue,
+                };
+
+                let si = sx + sy * src.width;
+                let di = dx + dy * self.width;
+                let (sa, da) = (src.alpha[si], self.alpha[di]);
+                let a = sa + da * (1.0 - sa);
+
+                self.canvas[di] = if a > 0.0 {
+                    (src.canvas[si].clone() * sa + self.canvas[di].clone() * (da * (1.0 - sa))) * (1.0 / a)
+                } else {
+                    Color::BLACK
+                };
+                self.alpha[di] = a;
+            }
+        }
+    }
     
     /// Translate the given canvas into the __PPM__ file format.
     ///
@@ -166,4 +425,585 @@ impl Canvas {
 
         ppm
     }
+
+    /// Translate the given canvas into the __PPM__ file format using
+    /// `maxval` as the maximum color value.
+    ///
+    /// Each color channel is scaled between 0 and `maxval`. Every row of
+    /// the canvas starts on a new line and no line is longer than 70
+    /// characters, regardless of how wide the individual values are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    /// use sray::color::{Color, DefaultColors};
+    ///
+    /// let mut c = Canvas::new(3, 1);
+    /// c.write_pixel(0, 0, Color::RED);
+    /// c.write_pixel(1, 0, Color::AZURE);
+    ///
+    /// let body = "P3\n\
+    ///             3 1\n\
+    ///             65535\n\
+    ///             65535 0 0 0 32768 65535 0 0 0\n";
+    ///
+    /// assert_eq!(body, c.to_ppm_maxval(65535));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `maxval` is 0, which is not allowed by the PPM format.
+    pub fn to_ppm_maxval(&self, maxval: u16) -> String {
+        const MAX_LINE_LENGTH: usize = 70;
+        assert!(maxval > 0, "PPM maxval must be at least 1");
+        let scale = maxval as f64;
+
+        // Build header
+        let mut ppm = format!("P3\n{} {}\n{}\n", self.width, self.height, maxval);
+
+        // Process body
+        for row in self.canvas.chunks(self.width.max(1)) {
+            let mut line = String::new();
+
+            for color in row {
+                for channel in &[color.r(), color.g(), color.b()] {
+                    let token = ((channel * scale).ceil() as u32)
+                        .clamp(0, maxval as u32)
+                        .to_string();
+
+                    // Wrap the line before it exceeds the maximum length
+                    if line.is_empty() {
+                        line += &token;
+                    } else if line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+                        ppm += &line;
+                        ppm += "\n";
+                        line = token;
+                    } else {
+                        line += " ";
+                        line += &token;
+                    }
+                }
+            }
+
+            ppm += &line;
+            ppm += "\n";
+        }
+
+        ppm
+    }
+
+    /// Count the pixels of the given canvas per luminance range.
+    ///
+    /// The range `[0, 1]` is split into `buckets` equally sized buckets.
+    /// Luminance values outside of that range are counted in the first
+    /// or last bucket, respectively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    /// use sray::color::Color;
+    ///
+    /// let mut c = Canvas::new(2, 2);
+    /// c.write_pixel(0, 0, Color::new(1.0, 1.0, 1.0));
+    /// c.write_pixel(1, 0, Color::new(0.6, 0.6, 0.6));
+    ///
+    /// assert_eq!(vec![2, 0, 1, 1], c.luminance_histogram(4));
+    /// ```
+    pub fn luminance_histogram(&self, buckets: usize) -> Vec<usize> {
+        let mut histogram = vec![0; buckets];
+        if buckets == 0 {
+            return histogram;
+        }
+
+        for color in &self.canvas {
+            let luminance = color.luminance().clamp(0.0, 1.0);
+            histogram[((luminance * buckets as f64) as usize).min(buckets - 1)] += 1;
+        }
+
+        histogram
+    }
+
+    /// Create a correctly exposed copy of the given canvas.
+    ///
+    /// All colors are scaled so that the 95th percentile of the pixel
+    /// luminance maps to 1. Brighter pixels (e.g. highlights) may end up
+    /// above 1 and get clipped on output. A completely black canvas is
+    /// returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    /// use sray::color::Color;
+    ///
+    /// let c = Canvas::from_pixels(2, 1, vec![Color::new(0.1, 0.2, 0.05); 2]).unwrap();
+    /// let exposed = c.auto_exposure();
+    ///
+    /// assert!((exposed.pixel_at(0, 0).unwrap().luminance() - 1.0).abs() < 1e-10);
+    /// ```
+    pub fn auto_exposure(&self) -> Canvas {
+        const PERCENTILE: f64 = 0.95;
+        const TARGET: f64 = 1.0;
+
+        let mut luminances: Vec<f64> = self.canvas.iter().map(Color::luminance).collect();
+        luminances.sort_by(|a, b| a.total_cmp(b));
+
+        let reference = match luminances.len() {
+            0 => 0.0,
+            n => luminances[((n as f64 * PERCENTILE).ceil() as usize).clamp(1, n) - 1],
+        };
+        let exposure = if reference > 0.0 { TARGET / reference } else { 1.0 };
+
+        Canvas {
+            canvas: self.canvas.iter().map(|c| c.clone() * exposure).collect(),
+            alpha: self.alpha.clone(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Render the given canvas as ASCII art that is `width_chars` characters wide.
+    ///
+    /// The canvas is downsampled into cells, each twice as high as wide to
+    /// account for the shape of terminal characters, and the average
+    /// luminance of each cell is mapped to a character from `" .:-=+*#%@"`.
+    /// Every line, including the last one, ends with a newline character.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    /// use sray::color::Color;
+    ///
+    /// let mut c = Canvas::new(8, 8);
+    /// for x in 4..8 {
+    ///     for y in 0..8 {
+    ///         c.write_pixel(x, y, Color::new(1.0, 1.0, 1.0));
+    ///     }
+    /// }
+    ///
+    /// assert_eq!("  @@\n  @@\n", c.to_ascii(4));
+    /// ```
+    pub fn to_ascii(&self, width_chars: usize) -> String {
+        const RAMP: &[u8] = b" .:-=+*#%@";
+
+        if width_chars == 0 || self.width == 0 || self.height == 0 {
+            return String::new();
+        }
+
+        let cell_width = self.width as f64 / width_chars as f64;
+        let rows = ((self.height as f64 / (2.0 * cell_width)).round() as usize).max(1);
+        let cell_height = self.height as f64 / rows as f64;
+
+        // Map a cell index to the range of pixels it covers
+        let span = |i: usize, size: f64, max: usize| {
+            let start = ((i as f64 * size) as usize).min(max - 1);
+            let end = (((i + 1) as f64 * size) as usize).clamp(start + 1, max);
+            start..end
+        };
+
+        let mut ascii = String::with_capacity(rows * (width_chars + 1));
+        for row in 0..rows {
+            for col in 0..width_chars {
+                let mut sum = 0.0;
+                let mut count = 0;
+
+                for y in span(row, cell_height, self.height) {
+                    for x in span(col, cell_width, self.width) {
+                        sum += self.canvas[x + y * self.width].luminance();
+                        count += 1;
+                    }
+                }
+
+                let luminance = (sum / count as f64).clamp(0.0, 1.0);
+                ascii.push(RAMP[(luminance * (RAMP.len() - 1) as f64).round() as usize] as char);
+            }
+            ascii.push('\n');
+        }
+
+        ascii
+    }
+
+    /// Save the given canvas as an __OpenEXR__ image at `path`.
+    ///
+    /// The colors and alpha values are stored as 32 bit floats without
+    /// clamping, i.e. the full dynamic range of the canvas is preserved.
+    ///
+    /// This requires the `exr` feature.
+    #[cfg(feature = "exr")]
+    pub fn save_exr<P: AsRef<Path>>(&self, path: P) -> Result<(), ExrError> {
+        exr::prelude::write_rgba_file(path, self.width, self.height, |x, y| {
+            let i = x + y * self.width;
+            let color = &self.canvas[i];
+
+            (color.r() as f32, color.g() as f32, color.b() as f32, self.alpha[i] as f32)
+        })
+    }
+
+    /// Translate the given canvas into a __PNG__ image with an alpha channel.
+    ///
+    /// Each pixel is stored as four 8 bit values (red, green, blue and
+    /// alpha). The image data is stored uncompressed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    ///
+    /// let c = Canvas::new(10, 20);
+    /// let png = c.to_png_rgba();
+    ///
+    /// assert_eq!(&[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a], &png[..8]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width or height of the canvas is 0 or greater than
+    /// `2^31 - 1`, because such an image can't be represented as PNG.
+    pub fn to_png_rgba(&self) -> Vec<u8> {
+        const SCALE: f64 = 255.0;
+        const MAX_SIZE: usize = i32::MAX as usize;
+        assert!(
+            (1..=MAX_SIZE).contains(&self.width) && (1..=MAX_SIZE).contains(&self.height),
+            "a PNG image must be between 1 and 2^31 - 1 pixels wide and high"
+        );
+        let to_u8 = |c: f64| ((c * SCALE).ceil() as u32).clamp(0, 255) as u8;
+
+        // Every scanline starts with the filter type (none)
+        let mut raw = Vec::with_capacity(self.height * (1 + self.width * 4));
+        for (colors, alphas) in self.canvas.chunks(self.width).zip(self.alpha.chunks(self.width)) {
+            raw.push(0);
+            for (color, alpha) in colors.iter().zip(alphas) {
+                raw.extend_from_slice(&[to_u8(color.r()), to_u8(color.g()), to_u8(color.b()), to_u8(*alpha)]);
+            }
+        }
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&(self.width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // Bit depth 8, color type RGBA, default compression, filter and no interlacing
+        ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        write_png_chunk(&mut png, b"IHDR", &ihdr);
+        write_png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        write_png_chunk(&mut png, b"IEND", &[]);
+
+        png
+    }
+}
+
+
+/// Append a PNG chunk consisting of length, type, data and checksum.
+fn write_png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+
+    let crc = crc32(kind.iter().chain(data));
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wrap `data` into a zlib stream using uncompressed (stored) deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = 0xffff;
+
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[0x01, 0x00, 0x00, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    // Adler-32 checksum of the uncompressed data
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend_from_slice(&((b << 16) | a).to_be_bytes());
+
+    out
+}
+
+/// Calculate the CRC-32 checksum used by PNG chunks.
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Canvas, CanvasError, crc32};
+    use crate::color::{Color, DefaultColors};
+
+    #[test]
+    fn blitting_respects_source_and_destination_alpha() {
+        let mut dst = Canvas::new_transparent(2, 1);
+        dst.write_pixel(0, 0, Color::BLUE);
+        dst.write_alpha(0, 0, 0.5);
+
+        let mut src = Canvas::new_transparent(2, 1);
+        src.write_pixel(0, 0, Color::RED);
+        src.write_alpha(0, 0, 0.5);
+
+        dst.blit(&src, 0, 0);
+
+        // 50% red over 50% blue
+        assert_eq!(Some(0.75), dst.alpha_at(0, 0));
+        assert_eq!(&Color::new(2.0 / 3.0, 0.0, 1.0 / 3.0), dst.pixel_at(0, 0).unwrap());
+
+        // Transparent over transparent stays transparent
+        assert_eq!(Some(0.0), dst.alpha_at(1, 0));
+
+        // An opaque source replaces the destination
+        let opaque = Canvas::from_pixels(1, 1, vec![Color::GREEN]).unwrap();
+        dst.blit(&opaque, 0, 0);
+        assert_eq!(Some(1.0), dst.alpha_at(0, 0));
+        assert_eq!(&Color::GREEN, dst.pixel_at(0, 0).unwrap());
+    }
+
+    #[test]
+    fn blitting_far_outside_of_the_canvas_is_ignored() {
+        let mut dst = Canvas::new(2, 1);
+        let src = Canvas::from_pixels(2, 1, vec![Color::RED, Color::RED]).unwrap();
+
+        dst.blit(&src, usize::MAX, 0);
+        dst.blit(&src, 0, usize::MAX);
+
+        assert_eq!(&Color::BLACK, dst.pixel_at(0, 0).unwrap());
+        assert_eq!(&Color::BLACK, dst.pixel_at(1, 0).unwrap());
+    }
+
+    #[test]
+    fn ascii_art_uses_ramp_extremes_and_requested_width() {
+        let white = Canvas::from_pixels(40, 20, vec![Color::new(1.0, 1.0, 1.0); 800]).unwrap();
+        let black = Canvas::new(40, 20);
+
+        for (canvas, expected) in &[(white, '@'), (black, ' ')] {
+            for width in &[1, 7, 20, 40, 80] {
+                let ascii = canvas.to_ascii(*width);
+
+                assert!(ascii.ends_with('\n'));
+                for line in ascii.lines() {
+                    assert_eq!(*width, line.chars().count());
+                    assert!(line.chars().all(|c| c == *expected));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ascii_art_preserves_aspect_ratio() {
+        let c = Canvas::new(40, 20);
+
+        // Cells are twice as high as wide
+        assert_eq!(5, c.to_ascii(20).lines().count());
+        assert_eq!(10, c.to_ascii(40).lines().count());
+        assert_eq!(1, c.to_ascii(1).lines().count());
+        assert_eq!("", c.to_ascii(0));
+    }
+
+    #[test]
+    fn histogram_buckets_sum_to_pixel_count() {
+        let mut c = Canvas::new(7, 5);
+        for i in 0..35 {
+            let v = i as f64 / 17.0 - 0.5;
+            c.write_pixel(i % 7, i / 7, Color::new(v, v, v));
+        }
+
+        for buckets in &[1, 3, 10, 64] {
+            let histogram = c.luminance_histogram(*buckets);
+
+            assert_eq!(*buckets, histogram.len());
+            assert_eq!(35, histogram.iter().sum::<usize>());
+        }
+        assert!(c.luminance_histogram(0).is_empty());
+    }
+
+    #[test]
+    fn auto_exposure_scales_mid_gray_toward_target() {
+        let gray = Canvas::from_pixels(4, 4, vec![Color::new(0.25, 0.25, 0.25); 16]).unwrap();
+        let exposed = gray.auto_exposure();
+
+        assert_eq!(4, exposed.width());
+        assert_eq!(4, exposed.height());
+        for y in 0..4 {
+            for x in 0..4 {
+                assert_eq!(&Color::new(1.0, 1.0, 1.0), exposed.pixel_at(x, y).unwrap());
+            }
+        }
+
+        let black = Canvas::new(2, 2);
+        assert_eq!(&Color::BLACK, black.auto_exposure().pixel_at(1, 1).unwrap());
+    }
+
+    #[test]
+    fn auto_exposure_ignores_the_brightest_pixels() {
+        let mut pixels = vec![Color::new(0.5, 0.5, 0.5); 100];
+        pixels[0] = Color::new(10.0, 10.0, 10.0);
+        let c = Canvas::from_pixels(10, 10, pixels).unwrap();
+        let exposed = c.auto_exposure();
+
+        assert_eq!(&Color::new(1.0, 1.0, 1.0), exposed.pixel_at(5, 5).unwrap());
+        assert_eq!(&Color::new(20.0, 20.0, 20.0), exposed.pixel_at(0, 0).unwrap());
+    }
+
+    #[cfg(feature = "exr")]
+    #[test]
+    fn exr_keeps_values_above_one() {
+        use exr::prelude::read_first_rgba_layer_from_file;
+
+        let mut c = Canvas::new(3, 2);
+        c.write_pixel(1, 1, Color::new(2.5, 0.25, 17.0));
+        c.write_alpha(2, 0, 0.5);
+
+        let path = std::env::temp_dir().join(format!("sray-{}-exr-roundtrip.exr", std::process::id()));
+        c.save_exr(&path).unwrap();
+
+        let width = c.width();
+        let image = read_first_rgba_layer_from_file(
+            &path,
+            |resolution, _| vec![(0.0, 0.0, 0.0, 0.0); resolution.width() * resolution.height()],
+            move |pixels: &mut Vec<(f32, f32, f32, f32)>, position, rgba: (f32, f32, f32, f32)| {
+                pixels[position.x() + position.y() * width] = rgba;
+            },
+        ).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let pixels = &image.layer_data.channel_data.pixels;
+        assert_eq!(width * c.height(), pixels.len());
+        assert_eq!((2.5, 0.25, 17.0, 1.0), pixels[1 + width]);
+        assert_eq!((0.0, 0.0, 0.0, 0.5), pixels[2]);
+    }
+
+    #[test]
+    fn crc32_matches_reference_value() {
+        assert_eq!(0xcbf4_3926, crc32(b"123456789"));
+        assert_eq!(0xae42_6082, crc32(b"IEND"));
+    }
+
+    #[test]
+    #[should_panic(expected = "PNG")]
+    fn png_of_an_empty_canvas_is_rejected() {
+        Canvas::new(3, 0).to_png_rgba();
+    }
+
+    #[test]
+    fn png_contains_rgba_pixel_data() {
+        let mut c = Canvas::new_transparent(2, 1);
+        c.write_pixel(0, 0, Color::RED);
+        c.write_alpha(0, 0, 1.0);
+        c.write_pixel(1, 0, Color::AZURE);
+        c.write_alpha(1, 0, 0.5);
+
+        let png = c.to_png_rgba();
+
+        // IHDR
+        assert_eq!(&[0, 0, 0, 13], &png[8..12]);
+        assert_eq!(b"IHDR", &png[12..16]);
+        assert_eq!(&[0, 0, 0, 2, 0, 0, 0, 1, 8, 6, 0, 0, 0], &png[16..29]);
+
+        // IDAT: zlib header, one final stored block and the scanline
+        assert_eq!(b"IDAT", &png[37..41]);
+        assert_eq!(&[0x78, 0x01, 0x01, 9, 0, !9, 0xff], &png[41..48]);
+        assert_eq!(&[0, 255, 0, 0, 255, 0, 128, 255, 128], &png[48..57]);
+
+        // IEND
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn creating_a_canvas_from_pixels_with_wrong_length_fails() {
+        let pixels = vec![Color::new(0.0, 0.0, 0.0); 5];
+
+        assert_eq!(
+            Some(CanvasError::SizeMismatch { expected: 6, actual: 5 }),
+            Canvas::from_pixels(3, 2, pixels).err()
+        );
+        assert_eq!(
+            Some(CanvasError::SizeMismatch { expected: 18, actual: 17 }),
+            Canvas::from_rgb8(3, 2, &[0; 17]).err()
+        );
+    }
+
+    #[test]
+    fn creating_a_canvas_with_overflowing_dimensions_fails() {
+        assert_eq!(
+            Some(CanvasError::TooLarge { width: usize::MAX, height: 2 }),
+            Canvas::from_pixels(usize::MAX, 2, vec![]).err()
+        );
+        assert_eq!(
+            Some(CanvasError::TooLarge { width: usize::MAX / 2, height: 1 }),
+            Canvas::from_rgb8(usize::MAX / 2, 1, &[]).err()
+        );
+    }
+
+    #[test]
+    fn rgb8_bytes_round_trip_through_ppm() {
+        let bytes: Vec<u8> = (0..=255).collect::<Vec<u8>>().repeat(3);
+        let c = Canvas::from_rgb8(16, 16, &bytes).unwrap();
+
+        let ppm = c.to_ppm_maxval(255);
+        let values: Vec<u8> = ppm
+            .lines()
+            .skip(3)
+            .flat_map(|line| line.split(' '))
+            .map(|v| v.parse().unwrap())
+            .collect();
+
+        assert_eq!(bytes, values);
+    }
+
+    #[test]
+    #[should_panic(expected = "maxval")]
+    fn ppm_with_zero_maxval_is_rejected() {
+        Canvas::new(2, 1).to_ppm_maxval(0);
+    }
+
+    #[test]
+    fn ppm_with_16_bit_maxval_is_scaled_and_wrapped() {
+        let mut c = Canvas::new(10, 2);
+        for y in 0..2 {
+            for x in 0..10 {
+                c.write_pixel(x, y, Color::new(1.0, 0.8, 0.6));
+            }
+        }
+
+        let ppm = c.to_ppm_maxval(65535);
+        let mut lines = ppm.lines();
+
+        assert_eq!(Some("P3"), lines.next());
+        assert_eq!(Some("10 2"), lines.next());
+        assert_eq!(Some("65535"), lines.next());
+
+        let body: Vec<&str> = lines.collect();
+        assert!(body.iter().all(|line| line.len() <= 70));
+        assert_eq!("65535 52428 39321 65535 52428 39321 65535 52428 39321 65535 52428", body[0]);
+
+        let values: Vec<&str> = body.iter().flat_map(|line| line.split(' ')).collect();
+        assert_eq!(60, values.len());
+        assert!(values.chunks(3).all(|rgb| rgb == ["65535", "52428", "39321"]));
+        assert!(ppm.ends_with('\n'));
+    }
 }
@@ -1,5 +1,7 @@
-use std::ops;
+use core::ops;
 use super::misc::equal;
+#[cfg(not(any(feature = "std", test)))]
+use super::misc::Float;
 
 /// A color represented by its red, green and blue values.
 #[derive(Debug, Clone)]
@@ -28,6 +30,67 @@ impl Color {
         Color { r, g, b }
     }
 
+    /// Approximate the color of a black body at the given temperature in Kelvin.
+    ///
+    /// Uses Tanner Helland's curve fit of the black body spectrum, which is
+    /// reasonable between 1000K and 40000K. Temperatures outside of this
+    /// range are clamped to it and NaN is treated as neutral daylight
+    /// (6500K). The result is normalized such that the largest channel
+    /// is 1, e.g. to be used as light intensity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::color::Color;
+    ///
+    /// // Neutral daylight
+    /// let c = Color::from_kelvin(6500.0);
+    /// assert!(c.r() > 0.95 && c.g() > 0.95 && c.b() > 0.95);
+    ///
+    /// // Warm incandescent light
+    /// let c = Color::from_kelvin(2700.0);
+    /// assert_eq!(1.0, c.r());
+    /// assert!(c.b() < 0.5);
+    ///
+    /// // Cool overcast sky
+    /// let c = Color::from_kelvin(9000.0);
+    /// assert_eq!(1.0, c.b());
+    /// assert!(c.r() < c.b());
+    ///
+    /// // Temperatures below 1000K are clamped
+    /// assert_eq!(Color::from_kelvin(1000.0), Color::from_kelvin(-500.0));
+    /// assert_eq!(Color::from_kelvin(6500.0), Color::from_kelvin(f64::NAN));
+    /// ```
+    pub fn from_kelvin(temp: f64) -> Self {
+        let temp = if temp.is_nan() { 6500.0 } else { temp };
+        let t = temp.clamp(1000.0, 40000.0) / 100.0;
+
+        let r = if t <= 66.0 {
+            255.0
+        } else {
+            329.698727446 * (t - 60.0).powf(-0.1332047592)
+        };
+
+        let g = if t <= 66.0 {
+            99.4708025861 * t.ln() - 161.1195681661
+        } else {
+            288.1221695283 * (t - 60.0).powf(-0.0755148492)
+        };
+
+        let b = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.5177312231 * (t - 10.0).ln() - 305.0447927307
+        };
+
+        let (r, g, b) = (r.clamp(0.0, 255.0), g.clamp(0.0, 255.0), b.clamp(0.0, 255.0));
+        let max = r.max(g).max(b);
+
+        Color::new(r / max, g / max, b / max)
+    }
+
     pub fn r(&self) -> f64 {
         self.r
     }
@@ -39,6 +102,81 @@ impl Color {
     pub fn b(&self) -> f64 {
         self.b
     }
+
+    /// Calculate the relative luminance of the given color.
+    ///
+    /// The channels are weighted according to how sensitive the
+    /// human eye is to them (Rec. 709).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::color::{Color, DefaultColors};
+    ///
+    /// assert_eq!(0.0, Color::BLACK.luminance());
+    /// assert_eq!(1.0, Color::new(1.0, 1.0, 1.0).luminance());
+    /// assert!(Color::GREEN.luminance() > Color::RED.luminance());
+    /// ```
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    /// Decode a color given in sRGB into linear space.
+    ///
+    /// Lighting calculations must be done in linear space, so colors
+    /// authored in sRGB (e.g. textures) should be decoded first. This
+    /// uses the piecewise sRGB transfer function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::color::{Color, DefaultColors};
+    ///
+    /// assert_eq!(Color::BLACK, Color::BLACK.from_srgb());
+    /// assert_eq!(Color::new(1.0, 1.0, 1.0), Color::new(1.0, 1.0, 1.0).from_srgb());
+    ///
+    /// let c = Color::new(0.5, 0.5, 0.5).from_srgb();
+    /// assert!((c.r() - 0.214041).abs() < 1e-6);
+    /// ```
+    pub fn from_srgb(&self) -> Color {
+        fn decode(c: f64) -> f64 {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+
+        Color::new(decode(self.r), decode(self.g), decode(self.b))
+    }
+
+    /// Encode a color given in linear space into sRGB.
+    ///
+    /// This is the inverse of [`Color::from_srgb`] and should be applied
+    /// to the final color before it's written to an image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::color::{Color, DefaultColors};
+    ///
+    /// assert_eq!(Color::BLACK, Color::BLACK.to_srgb());
+    /// assert_eq!(Color::new(1.0, 1.0, 1.0), Color::new(1.0, 1.0, 1.0).to_srgb());
+    ///
+    /// let c = Color::new(0.2, 0.5, 0.8);
+    /// assert_eq!(c, c.from_srgb().to_srgb());
+    /// ```
+    pub fn to_srgb(&self) -> Color {
+        fn encode(c: f64) -> f64 {
+            if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        }
+
+        Color::new(encode(self.r), encode(self.g), encode(self.b))
+    }
 }
 
 impl PartialEq for Color {
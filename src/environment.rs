@@ -0,0 +1,111 @@
+use std::f64::consts::PI;
+use super::canvas::Canvas;
+use super::color::{Color, DefaultColors};
+use super::math::Vector3;
+
+/// An environment map surrounding the whole scene.
+///
+/// The map is stored as an __equirectangular__ image, i.e. the x axis of
+/// the image covers the full circle around the y axis and the y axis of
+/// the image goes from straight up (top row) to straight down (bottom row).
+/// The center of the image lies in the direction of the positive z axis.
+pub struct EnvironmentMap {
+    image: Canvas,
+}
+
+impl EnvironmentMap {
+
+    /// Create a new environment map from an equirectangular image.
+    pub fn new(image: Canvas) -> Self {
+        Self { image }
+    }
+
+    /// Get the color of the environment in the given `direction`.
+    ///
+    /// The direction doesn't have to be normalized. An empty image or a
+    /// zero length direction yields black.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::canvas::Canvas;
+    /// use sray::color::{Color, DefaultColors};
+    /// use sray::environment::EnvironmentMap;
+    /// use sray::math::Vector3;
+    ///
+    /// // Blue sky above the horizon and green ground below it
+    /// let mut image = Canvas::new(4, 2);
+    /// for x in 0..4 {
+    ///     image.write_pixel(x, 0, Color::AZURE);
+    ///     image.write_pixel(x, 1, Color::GREEN);
+    /// }
+    /// let env = EnvironmentMap::new(image);
+    ///
+    /// assert_eq!(Color::AZURE, env.sample(&Vector3::new(0.0, 1.0, 0.0)));
+    /// assert_eq!(Color::GREEN, env.sample(&Vector3::new(0.0, -1.0, 0.0)));
+    /// ```
+    pub fn sample(&self, direction: &Vector3) -> Color {
+        let (width, height) = (self.image.width(), self.image.height());
+        let mag = direction.mag();
+
+        if width == 0 || height == 0 || mag == 0.0 {
+            return Color::BLACK;
+        }
+
+        let d = *direction / mag;
+        let u = 0.5 + d.x().atan2(d.z()) / (2.0 * PI);
+        let v = d.y().clamp(-1.0, 1.0).acos() / PI;
+
+        let x = ((u * width as f64) as usize).min(width - 1);
+        let y = ((v * height as f64) as usize).min(height - 1);
+
+        self.image.pixel_at(x, y).cloned().unwrap_or(Color::BLACK)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnvironmentMap;
+    use crate::canvas::Canvas;
+    use crate::color::{Color, DefaultColors};
+    use crate::math::Vector3;
+
+    fn map() -> EnvironmentMap {
+        // Each column and each row gets its own color channel value
+        let mut image = Canvas::new(8, 4);
+        for y in 0..4 {
+            for x in 0..8 {
+                image.write_pixel(x, y, Color::new(x as f64 / 8.0, y as f64 / 4.0, 0.0));
+            }
+        }
+        EnvironmentMap::new(image)
+    }
+
+    #[test]
+    fn up_and_down_sample_the_top_and_bottom_rows() {
+        let env = map();
+
+        assert_eq!(0.0, env.sample(&Vector3::new(0.0, 1.0, 0.0)).g());
+        assert_eq!(0.0, env.sample(&Vector3::new(0.3, 5.0, -0.2)).g());
+        assert_eq!(0.75, env.sample(&Vector3::new(0.0, -1.0, 0.0)).g());
+        assert_eq!(0.75, env.sample(&Vector3::new(-0.1, -3.0, 0.2)).g());
+    }
+
+    #[test]
+    fn horizontal_directions_wrap_around_the_image() {
+        let env = map();
+
+        // Forward is the center of the image, backward is its left/right edge
+        assert_eq!(Color::new(0.5, 0.5, 0.0), env.sample(&Vector3::new(0.0, 0.0, 1.0)));
+        assert_eq!(0.75, env.sample(&Vector3::new(1.0, 0.0, 0.0)).r());
+        assert_eq!(0.25, env.sample(&Vector3::new(-1.0, 0.0, 0.0)).r());
+        assert_eq!(0.875, env.sample(&Vector3::new(1e-9, 0.0, -1.0)).r());
+        assert_eq!(0.0, env.sample(&Vector3::new(-1e-9, 0.0, -1.0)).r());
+    }
+
+    #[test]
+    fn degenerate_inputs_yield_black() {
+        assert_eq!(Color::BLACK, map().sample(&Vector3::new(0.0, 0.0, 0.0)));
+        assert_eq!(Color::BLACK, EnvironmentMap::new(Canvas::new(0, 0)).sample(&Vector3::new(0.0, 1.0, 0.0)));
+    }
+}
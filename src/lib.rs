@@ -6,8 +6,29 @@
 //!
 //! This ray tracer uses the __left-handed coordinate system__, i.e. the x, y and z axes
 //! point right, up and forward, respectively.
+//!
+//! ## Features
+//!
+//! * `std` (default) - Enables everything that depends on the standard library,
+//!   i.e. the `canvas`, `camera`, `sampling` and `environment` modules.
+//! * `libm` - Provides the floating point functions required by the `math` and
+//!   `color` modules when building without `std` (`#![no_std]`).
+//! * `exr` - Enables saving a canvas as OpenEXR image (`Canvas::save_exr`).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or the `libm` feature must be enabled");
 
 pub mod math;
 pub mod color;
+#[cfg(feature = "std")]
 pub mod canvas;
+#[cfg(feature = "std")]
+pub mod camera;
+#[cfg(feature = "std")]
+pub mod sampling;
+#[cfg(feature = "std")]
+pub mod environment;
+pub mod prelude;
 mod misc;
@@ -1,15 +1,137 @@
-use std::ops;
+use core::convert::TryFrom;
+use core::{fmt, ops};
 use super::misc::equal;
+#[cfg(not(any(feature = "std", test)))]
+use super::misc::Float;
 
-#[derive(Debug)]
-struct Tuple4 {
-    x: f64,
-    y: f64,
-    z: f64,
-    w: f64,
+/// A four component vector.
+///
+/// `Vec4` is the homogeneous representation used by [`Point3`] and
+/// [`Vector3`] internally. The `w` component of a point is `1` and
+/// the `w` component of a vector is `0`.
+#[derive(Debug, Clone, Copy)]
+pub struct Vec4 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
 }
 
-impl ops::Add<Self> for Tuple4 {
+impl Vec4 {
+
+    /// Create a new four component vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Vec4;
+    ///
+    /// let v = Vec4::new(4.3, -4.2, 3.1, 1.0);
+    ///
+    /// assert_eq!(4.3, v.x);
+    /// assert_eq!(-4.2, v.y);
+    /// assert_eq!(3.1, v.z);
+    /// assert_eq!(1.0, v.w);
+    /// ```
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Calculate the dot product of two four component vectors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Vec4;
+    ///
+    /// let v1 = Vec4::new(1.0, 2.0, 3.0, 4.0);
+    /// let v2 = Vec4::new(2.0, 3.0, 4.0, 5.0);
+    ///
+    /// assert_eq!(40.0, v1.dot(&v2));
+    /// ```
+    pub fn dot(&self, _rhs: &Self) -> f64 {
+        self.x * _rhs.x +
+        self.y * _rhs.y +
+        self.z * _rhs.z +
+        self.w * _rhs.w
+    }
+
+    /// Calculate the length (magnitude) of the given vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Vec4;
+    ///
+    /// let v = Vec4::new(1.0, 2.0, 3.0, 4.0);
+    ///
+    /// assert_eq!(30.0_f64.sqrt(), v.length());
+    /// ```
+    pub fn length(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+}
+
+/// Errors that can occur while converting a [`Vec4`] into a point or vector.
+#[derive(Debug, PartialEq)]
+pub enum HomogeneousError {
+    /// The `w` component is `0`, i.e. the coordinates describe a
+    /// direction (a point at infinity) instead of a point.
+    PointAtInfinity,
+    /// The `w` component is not `0`, i.e. the coordinates describe
+    /// a point instead of a direction.
+    NotADirection,
+}
+
+impl fmt::Display for HomogeneousError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HomogeneousError::PointAtInfinity => write!(f, "w is 0, the coordinates describe a direction"),
+            HomogeneousError::NotADirection => write!(f, "w is not 0, the coordinates describe a point"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HomogeneousError {}
+
+impl From<Point3> for Vec4 {
+
+    /// Convert a point into its homogeneous representation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::{Point3, Vec4};
+    ///
+    /// let v = Vec4::from(Point3::new(1.0, 2.0, 3.0));
+    ///
+    /// assert_eq!(Vec4::new(1.0, 2.0, 3.0, 1.0), v);
+    /// ```
+    fn from(p: Point3) -> Self {
+        p.0
+    }
+}
+
+impl From<Vector3> for Vec4 {
+
+    /// Convert a vector into its homogeneous representation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::{Vector3, Vec4};
+    ///
+    /// let v = Vec4::from(Vector3::new(1.0, 2.0, 3.0));
+    ///
+    /// assert_eq!(Vec4::new(1.0, 2.0, 3.0, 0.0), v);
+    /// ```
+    fn from(v: Vector3) -> Self {
+        v.0
+    }
+}
+
+impl ops::Add<Self> for Vec4 {
     type Output = Self;
     
     fn add(self, _rhs: Self) -> Self::Output {
@@ -22,7 +144,7 @@ impl ops::Add<Self> for Tuple4 {
     }
 }
 
-impl ops::Sub<Self> for Tuple4 {
+impl ops::Sub<Self> for Vec4 {
     type Output = Self;
 
     fn sub(self, _rhs: Self) -> Self::Output {
@@ -35,7 +157,7 @@ impl ops::Sub<Self> for Tuple4 {
     }
 }
 
-impl ops::Neg for Tuple4 {
+impl ops::Neg for Vec4 {
     type Output = Self;
 
     fn neg(self) -> Self::Output {
@@ -48,7 +170,7 @@ impl ops::Neg for Tuple4 {
     }
 }
 
-impl ops::Mul<f64> for Tuple4 {
+impl ops::Mul<f64> for Vec4 {
     type Output = Self;
 
     fn mul(self, _rhs: f64) -> Self::Output {
@@ -61,7 +183,7 @@ impl ops::Mul<f64> for Tuple4 {
     }
 }
 
-impl ops::Div<f64> for Tuple4 {
+impl ops::Div<f64> for Vec4 {
     type Output = Self;
 
     fn div(self, _rhs: f64) -> Self::Output {
@@ -74,7 +196,7 @@ impl ops::Div<f64> for Tuple4 {
     }
 }
 
-impl PartialEq for Tuple4 {
+impl PartialEq for Vec4 {
     fn eq(&self, other: &Self) -> bool {
         equal(self.x, other.x) &&
         equal(self.y, other.y) &&
@@ -84,8 +206,8 @@ impl PartialEq for Tuple4 {
 }
 
 /// A point in 3d space.
-#[derive(Debug, PartialEq)]
-pub struct Point3(Tuple4);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point3(Vec4);
 
 impl Point3 {
 
@@ -103,7 +225,7 @@ impl Point3 {
     /// assert_eq!(3.1, p.z());
     /// ```
     pub fn new(x: f64, y: f64, z: f64) -> Self {
-        Self(Tuple4 { x, y, z, w: 1.0 })
+        Self(Vec4::new(x, y, z, 1.0))
     }
     
     /// Get the x coordinate of the given point.
@@ -120,6 +242,75 @@ impl Point3 {
     pub fn z(&self) -> f64 {
         self.0.z
     }
+
+    /// Get the component-wise minimum of two points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Point3;
+    ///
+    /// let p1 = Point3::new(1.0, 5.0, 3.0);
+    /// let p2 = Point3::new(4.0, 2.0, 6.0);
+    ///
+    /// assert_eq!(Point3::new(1.0, 2.0, 3.0), p1.min(&p2));
+    /// ```
+    pub fn min(&self, _rhs: &Self) -> Self {
+        Self::new(
+            self.x().min(_rhs.x()),
+            self.y().min(_rhs.y()),
+            self.z().min(_rhs.z())
+        )
+    }
+
+    /// Get the component-wise maximum of two points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Point3;
+    ///
+    /// let p1 = Point3::new(1.0, 5.0, 3.0);
+    /// let p2 = Point3::new(4.0, 2.0, 6.0);
+    ///
+    /// assert_eq!(Point3::new(4.0, 5.0, 6.0), p1.max(&p2));
+    /// ```
+    pub fn max(&self, _rhs: &Self) -> Self {
+        Self::new(
+            self.x().max(_rhs.x()),
+            self.y().max(_rhs.y()),
+            self.z().max(_rhs.z())
+        )
+    }
+}
+
+impl TryFrom<Vec4> for Point3 {
+    type Error = HomogeneousError;
+
+    /// Convert homogeneous coordinates into a point.
+    ///
+    /// The `x`, `y` and `z` components are divided by `w` (perspective
+    /// divide). Fails if `w` is `0`, because the coordinates describe a
+    /// direction in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use sray::math::{HomogeneousError, Point3, Vec4};
+    ///
+    /// let p = Point3::try_from(Vec4::new(2.0, 4.0, 6.0, 2.0));
+    ///
+    /// assert_eq!(Ok(Point3::new(1.0, 2.0, 3.0)), p);
+    /// assert_eq!(Err(HomogeneousError::PointAtInfinity), Point3::try_from(Vec4::new(1.0, 2.0, 3.0, 0.0)));
+    /// ```
+    fn try_from(v: Vec4) -> Result<Self, Self::Error> {
+        if v.w == 0.0 {
+            Err(HomogeneousError::PointAtInfinity)
+        } else {
+            Ok(Self::new(v.x / v.w, v.y / v.w, v.z / v.w))
+        }
+    }
 }
 
 impl ops::Add<Vector3> for Point3 {
@@ -196,8 +387,8 @@ impl ops::Sub<Vector3> for Point3 {
 }
 
 /// A vector in 3d space.
-#[derive(Debug, PartialEq)]
-pub struct Vector3(Tuple4);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector3(Vec4);
 
 impl Vector3 {
     
@@ -215,7 +406,7 @@ impl Vector3 {
     /// assert_eq!(3.1, v.z());
     /// ```
     pub fn new(x: f64, y: f64, z: f64) -> Self {
-        Self(Tuple4 { x, y, z, w: 0.0 })
+        Self(Vec4::new(x, y, z, 0.0))
     }
 
     /// Get the x coordinate of the given vector.
@@ -260,11 +451,7 @@ impl Vector3 {
     /// assert_eq!((14.0_f64).sqrt(), v5.mag());
     /// ```
     pub fn mag(&self) -> f64 {
-        (self.0.x.powi(2) + 
-         self.0.y.powi(2) +
-         self.0.z.powi(2) +
-         self.0.w.powi(2))
-            .sqrt()
+        self.0.length()
     }
     
     /// Normalize the given vector.
@@ -293,12 +480,7 @@ impl Vector3 {
     pub fn norm(self) -> Self {
         let mv = self.mag();
 
-        Self(Tuple4 {
-            x: self.0.x / mv,
-            y: self.0.y / mv,
-            z: self.0.z / mv,
-            w: self.0.w / mv
-        })
+        Self(self.0 / mv)
     }
     
     /// Calculate the dot product of two vectors.
@@ -324,10 +506,7 @@ impl Vector3 {
     /// assert_eq!(20.0, v1.dot(&v2));
     /// ```
     pub fn dot(&self, _rhs: &Self) -> f64 {
-        self.0.x * _rhs.0.x +
-        self.0.y * _rhs.0.y +
-        self.0.z * _rhs.0.z +
-        self.0.w * _rhs.0.w
+        self.0.dot(&_rhs.0)
     }
     
     /// Calculate the cross product of two vectors.
@@ -353,6 +532,89 @@ impl Vector3 {
             self.x() * _rhs.y() - self.y() * _rhs.x()
         )
     }
+
+    /// Get the absolute value of each component of the given vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Vector3;
+    ///
+    /// let v = Vector3::new(-1.0, 2.0, -3.0);
+    ///
+    /// assert_eq!(Vector3::new(1.0, 2.0, 3.0), v.abs());
+    /// ```
+    pub fn abs(&self) -> Self {
+        Self::new(self.x().abs(), self.y().abs(), self.z().abs())
+    }
+
+    /// Get the component-wise minimum of two vectors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Vector3;
+    ///
+    /// let v1 = Vector3::new(1.0, 5.0, 3.0);
+    /// let v2 = Vector3::new(4.0, 2.0, 6.0);
+    ///
+    /// assert_eq!(Vector3::new(1.0, 2.0, 3.0), v1.component_min(&v2));
+    /// ```
+    pub fn component_min(&self, _rhs: &Self) -> Self {
+        Self::new(
+            self.x().min(_rhs.x()),
+            self.y().min(_rhs.y()),
+            self.z().min(_rhs.z())
+        )
+    }
+
+    /// Get the component-wise maximum of two vectors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Vector3;
+    ///
+    /// let v1 = Vector3::new(1.0, 5.0, 3.0);
+    /// let v2 = Vector3::new(4.0, 2.0, 6.0);
+    ///
+    /// assert_eq!(Vector3::new(4.0, 5.0, 6.0), v1.component_max(&v2));
+    /// ```
+    pub fn component_max(&self, _rhs: &Self) -> Self {
+        Self::new(
+            self.x().max(_rhs.x()),
+            self.y().max(_rhs.y()),
+            self.z().max(_rhs.z())
+        )
+    }
+}
+
+impl TryFrom<Vec4> for Vector3 {
+    type Error = HomogeneousError;
+
+    /// Convert homogeneous coordinates into a vector in 3d space.
+    ///
+    /// Fails if `w` is not `0`, because the coordinates describe a
+    /// point in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use sray::math::{HomogeneousError, Vector3, Vec4};
+    ///
+    /// let v = Vector3::try_from(Vec4::new(1.0, 2.0, 3.0, 0.0));
+    ///
+    /// assert_eq!(Ok(Vector3::new(1.0, 2.0, 3.0)), v);
+    /// assert_eq!(Err(HomogeneousError::NotADirection), Vector3::try_from(Vec4::new(1.0, 2.0, 3.0, 1.0)));
+    /// ```
+    fn try_from(v: Vec4) -> Result<Self, Self::Error> {
+        if v.w == 0.0 {
+            Ok(Self::new(v.x, v.y, v.z))
+        } else {
+            Err(HomogeneousError::NotADirection)
+        }
+    }
 }
 
 impl ops::Add<Self> for Vector3 {
@@ -460,14 +722,318 @@ impl ops::Div<f64> for Vector3 {
 }
 
 
+
+/// Build an orthonormal basis around the given `normal`.
+///
+/// Returns two tangents and the normalized `normal`, i.e. three mutually
+/// perpendicular unit vectors. The tangents are derived without a helper
+/// axis, so there is no degenerate case for normals close to an axis.
+///
+/// # Examples
+///
+/// ```
+/// use sray::math::{basis_from_normal, Vector3};
+///
+/// let (t, b, n) = basis_from_normal(Vector3::new(0.0, 2.0, 0.0));
+///
+/// assert_eq!(Vector3::new(0.0, 1.0, 0.0), n);
+/// assert!(t.dot(&n).abs() < 1e-10);
+/// assert!(b.dot(&n).abs() < 1e-10);
+/// assert!(t.dot(&b).abs() < 1e-10);
+/// ```
+pub fn basis_from_normal(normal: Vector3) -> (Vector3, Vector3, Vector3) {
+    let n = normal.norm();
+
+    // Duff et al., "Building an Orthonormal Basis, Revisited"
+    let sign = 1.0_f64.copysign(n.z());
+    let a = -1.0 / (sign + n.z());
+    let b = n.x() * n.y() * a;
+    let tangent = Vector3::new(1.0 + sign * n.x() * n.x() * a, sign * b, -sign * n.x());
+    let bitangent = Vector3::new(b, sign + n.y() * n.y() * a, -n.y());
+
+    (tangent, bitangent, n)
+}
+
+/// Solve the quadratic equation `a * t^2 + b * t + c = 0`.
+///
+/// Returns both real roots in ascending order, or `None` if there is no
+/// real solution. A double root is returned twice. If `a` is zero the
+/// equation is solved as a linear one.
+///
+/// The roots are computed using a numerically stable form that avoids
+/// the cancellation of the textbook formula when `b^2` is much larger
+/// than `4ac`.
+///
+/// # Examples
+///
+/// ```
+/// use sray::math::solve_quadratic;
+///
+/// assert_eq!(Some((-3.0, 2.0)), solve_quadratic(1.0, 1.0, -6.0));
+/// assert_eq!(Some((1.0, 1.0)), solve_quadratic(1.0, -2.0, 1.0));
+/// assert_eq!(None, solve_quadratic(1.0, 0.0, 1.0));
+/// ```
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Option<(f64, f64)> {
+    if a == 0.0 {
+        if b == 0.0 {
+            return None;
+        }
+        let t = -c / b;
+        return Some((t, t));
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    // Both b and the square root have the same sign, so they can't cancel
+    let q = -0.5 * (b + discriminant.sqrt().copysign(b));
+    if q == 0.0 {
+        // Only possible if b and c are zero
+        return Some((0.0, 0.0));
+    }
+
+    let (t0, t1) = (q / a, c / q);
+    if t0 <= t1 {
+        Some((t0, t1))
+    } else {
+        Some((t1, t0))
+    }
+}
+
+/// A 2x2 matrix.
+#[derive(Debug, Clone, Copy)]
+pub struct Matrix2 {
+    m: [[f64; 2]; 2],
+}
+
+impl Matrix2 {
+
+    /// Create a 2x2 matrix from its rows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Matrix2;
+    ///
+    /// let m = Matrix2::new([
+    ///     [-3.0, 5.0],
+    ///     [1.0, -2.0],
+    /// ]);
+    ///
+    /// assert_eq!(-3.0, m.at(0, 0));
+    /// assert_eq!(5.0, m.at(0, 1));
+    /// assert_eq!(1.0, m.at(1, 0));
+    /// assert_eq!(-2.0, m.at(1, 1));
+    /// ```
+    pub fn new(m: [[f64; 2]; 2]) -> Self {
+        Self { m }
+    }
+
+    /// Get the element at the given `row` and `col`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is out of bounds.
+    pub fn at(&self, row: usize, col: usize) -> f64 {
+        self.m[row][col]
+    }
+
+    /// Calculate the determinant of the given matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Matrix2;
+    ///
+    /// let m = Matrix2::new([
+    ///     [1.0, 5.0],
+    ///     [-3.0, 2.0],
+    /// ]);
+    ///
+    /// assert_eq!(17.0, m.determinant());
+    /// ```
+    pub fn determinant(&self) -> f64 {
+        self.m[0][0] * self.m[1][1] - self.m[0][1] * self.m[1][0]
+    }
+}
+
+impl PartialEq for Matrix2 {
+    fn eq(&self, other: &Self) -> bool {
+        self.m.iter().flatten()
+            .zip(other.m.iter().flatten())
+            .all(|(lhs, rhs)| equal(*lhs, *rhs))
+    }
+}
+
+/// A 3x3 matrix.
+#[derive(Debug, Clone, Copy)]
+pub struct Matrix3 {
+    m: [[f64; 3]; 3],
+}
+
+impl Matrix3 {
+
+    /// Create a 3x3 matrix from its rows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Matrix3;
+    ///
+    /// let m = Matrix3::new([
+    ///     [-3.0, 5.0, 0.0],
+    ///     [1.0, -2.0, -7.0],
+    ///     [0.0, 1.0, 1.0],
+    /// ]);
+    ///
+    /// assert_eq!(-3.0, m.at(0, 0));
+    /// assert_eq!(-2.0, m.at(1, 1));
+    /// assert_eq!(1.0, m.at(2, 2));
+    /// ```
+    pub fn new(m: [[f64; 3]; 3]) -> Self {
+        Self { m }
+    }
+
+    /// Get the element at the given `row` and `col`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is out of bounds.
+    pub fn at(&self, row: usize, col: usize) -> f64 {
+        self.m[row][col]
+    }
+
+    /// Remove the given `row` and `col` from the matrix.
+    ///
+    /// The submatrix of a 3x3 matrix is a 2x2 matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::{Matrix2, Matrix3};
+    ///
+    /// let m = Matrix3::new([
+    ///     [1.0, 5.0, 0.0],
+    ///     [-3.0, 2.0, 7.0],
+    ///     [0.0, 6.0, -3.0],
+    /// ]);
+    ///
+    /// assert_eq!(Matrix2::new([[-3.0, 2.0], [0.0, 6.0]]), m.submatrix(0, 2));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is out of bounds.
+    pub fn submatrix(&self, row: usize, col: usize) -> Matrix2 {
+        assert!(row < 3 && col < 3, "submatrix index out of bounds");
+
+        let mut m = [[0.0; 2]; 2];
+        let rows = (0..3).filter(|r| *r != row);
+
+        for (i, r) in rows.enumerate() {
+            let cols = (0..3).filter(|c| *c != col);
+
+            for (j, c) in cols.enumerate() {
+                m[i][j] = self.m[r][c];
+            }
+        }
+
+        Matrix2::new(m)
+    }
+
+    /// Calculate the minor of the element at `row` and `col`.
+    ///
+    /// The minor is the determinant of the submatrix at `row` and `col`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Matrix3;
+    ///
+    /// let m = Matrix3::new([
+    ///     [3.0, 5.0, 0.0],
+    ///     [2.0, -1.0, -7.0],
+    ///     [6.0, -1.0, 5.0],
+    /// ]);
+    ///
+    /// assert_eq!(25.0, m.submatrix(1, 0).determinant());
+    /// assert_eq!(25.0, m.minor(1, 0));
+    /// ```
+    pub fn minor(&self, row: usize, col: usize) -> f64 {
+        self.submatrix(row, col).determinant()
+    }
+
+    /// Calculate the cofactor of the element at `row` and `col`.
+    ///
+    /// The cofactor is the minor, negated if `row + col` is odd.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Matrix3;
+    ///
+    /// let m = Matrix3::new([
+    ///     [3.0, 5.0, 0.0],
+    ///     [2.0, -1.0, -7.0],
+    ///     [6.0, -1.0, 5.0],
+    /// ]);
+    ///
+    /// assert_eq!(-12.0, m.minor(0, 0));
+    /// assert_eq!(-12.0, m.cofactor(0, 0));
+    /// assert_eq!(25.0, m.minor(1, 0));
+    /// assert_eq!(-25.0, m.cofactor(1, 0));
+    /// ```
+    pub fn cofactor(&self, row: usize, col: usize) -> f64 {
+        let minor = self.minor(row, col);
+
+        if (row + col) & 1 == 0 {
+            minor
+        } else {
+            -minor
+        }
+    }
+
+    /// Calculate the determinant of the given matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::math::Matrix3;
+    ///
+    /// let m = Matrix3::new([
+    ///     [1.0, 2.0, 6.0],
+    ///     [-5.0, 8.0, -4.0],
+    ///     [2.0, 6.0, 4.0],
+    /// ]);
+    ///
+    /// assert_eq!(56.0, m.cofactor(0, 0));
+    /// assert_eq!(12.0, m.cofactor(0, 1));
+    /// assert_eq!(-46.0, m.cofactor(0, 2));
+    /// assert_eq!(-196.0, m.determinant());
+    /// ```
+    pub fn determinant(&self) -> f64 {
+        (0..3).map(|col| self.m[0][col] * self.cofactor(0, col)).sum()
+    }
+}
+
+impl PartialEq for Matrix3 {
+    fn eq(&self, other: &Self) -> bool {
+        self.m.iter().flatten()
+            .zip(other.m.iter().flatten())
+            .all(|(lhs, rhs)| equal(*lhs, *rhs))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Tuple4};
+    use super::{Vec4, Vector3, basis_from_normal, solve_quadratic};
 
     #[test]
     fn adding_two_tuples() {
-        let t1 = Tuple4 { x: 3.0, y: -2.0, z: 5.0, w: 1.0 };
-        let t2 = Tuple4 { x: -2.0, y: 3.0, z: 1.0, w: 0.0 };
+        let t1 = Vec4 { x: 3.0, y: -2.0, z: 5.0, w: 1.0 };
+        let t2 = Vec4 { x: -2.0, y: 3.0, z: 1.0, w: 0.0 };
         let expected = t1 + t2; 
 
         assert_eq!(1.0, expected.x);
@@ -478,8 +1044,8 @@ mod tests {
 
     #[test]
     fn subtracting_two_tuples() {
-        let t1 = Tuple4 { x: 3.0, y: 2.0, z: 1.0, w: 1.0 };
-        let t2 = Tuple4 { x: 5.0, y: 6.0, z: 7.0, w: 0.0 };
+        let t1 = Vec4 { x: 3.0, y: 2.0, z: 1.0, w: 1.0 };
+        let t2 = Vec4 { x: 5.0, y: 6.0, z: 7.0, w: 0.0 };
         let expected = t1 - t2; 
 
         assert_eq!(-2.0, expected.x);
@@ -490,9 +1056,9 @@ mod tests {
 
     #[test]
     fn comparing_two_tuples() {
-        let t1 = Tuple4 { x: 3.0, y: 2.0, z: 1.0, w: 1.0 };
-        let t2 = Tuple4 { x: 3.0, y: 2.0, z: 1.0, w: 1.0 };
-        let t3 = Tuple4 { x: 5.0, y: 6.0, z: 7.0, w: 0.0 };
+        let t1 = Vec4 { x: 3.0, y: 2.0, z: 1.0, w: 1.0 };
+        let t2 = Vec4 { x: 3.0, y: 2.0, z: 1.0, w: 1.0 };
+        let t3 = Vec4 { x: 5.0, y: 6.0, z: 7.0, w: 0.0 };
         
         assert!(t1 == t2);
         assert!(t2 == t1);
@@ -502,22 +1068,73 @@ mod tests {
 
     #[test]
     fn multiplying_a_tuple_by_a_scalar() {
-        let t = Tuple4 { x: 1.0, y: -2.0, z: 3.0, w: -4.0 };
+        let t = Vec4 { x: 1.0, y: -2.0, z: 3.0, w: -4.0 };
 
-        assert_eq!(Tuple4{ x: 3.5, y: -7.0, z: 10.5, w: -14.0 }, t * 3.5);
+        assert_eq!(Vec4{ x: 3.5, y: -7.0, z: 10.5, w: -14.0 }, t * 3.5);
     }
 
     #[test]
     fn multiplying_a_tuple_by_a_fraction() {
-        let t = Tuple4 { x: 1.0, y: -2.0, z: 3.0, w: -4.0 };
+        let t = Vec4 { x: 1.0, y: -2.0, z: 3.0, w: -4.0 };
 
-        assert_eq!(Tuple4{ x: 0.5, y: -1.0, z: 1.5, w: -2.0 }, t * 0.5);
+        assert_eq!(Vec4{ x: 0.5, y: -1.0, z: 1.5, w: -2.0 }, t * 0.5);
     }
 
     #[test]
     fn dividing_a_tuple_by_a_scalar() {
-        let t = Tuple4 { x: 1.0, y: -2.0, z: 3.0, w: -4.0 };
+        let t = Vec4 { x: 1.0, y: -2.0, z: 3.0, w: -4.0 };
+
+        assert_eq!(Vec4{ x: 0.5, y: -1.0, z: 1.5, w: -2.0 }, t / 2.0);
+    }
+
+    #[test]
+    fn basis_from_normal_is_orthonormal() {
+        let normals = [
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(1e-12, 1e-12, -1.0),
+            Vector3::new(1e-7, 0.0, 1.0),
+            Vector3::new(-1.0, 1.0, 0.0),
+            Vector3::new(1.0, -2.0, 3.0),
+        ];
+        let is_unit = |v: &Vector3| (v.mag() - 1.0).abs() < 1e-10;
+
+        for normal in normals {
+            let (t, b, n) = basis_from_normal(normal);
+
+            assert!(is_unit(&t) && is_unit(&b) && is_unit(&n));
+            assert!(t.dot(&b).abs() < 1e-10);
+            assert!(t.dot(&n).abs() < 1e-10);
+            assert!(b.dot(&n).abs() < 1e-10);
+            assert_eq!(normal.norm(), n);
+        }
+    }
+
+    #[test]
+    fn solving_quadratics_with_known_roots() {
+        assert_eq!(Some((-3.0, 2.0)), solve_quadratic(1.0, 1.0, -6.0));
+        assert_eq!(Some((-1.0, 1.0)), solve_quadratic(-2.0, 0.0, 2.0));
+        assert_eq!(Some((0.0, 0.0)), solve_quadratic(3.0, 0.0, 0.0));
+        assert_eq!(Some((0.0, 4.0)), solve_quadratic(1.0, -4.0, 0.0));
+        assert_eq!(Some((-2.0, -2.0)), solve_quadratic(0.0, 2.0, 4.0));
+        assert_eq!(None, solve_quadratic(1.0, 2.0, 3.0));
+        assert_eq!(None, solve_quadratic(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn solving_quadratics_with_near_cancellation() {
+        // The roots of t^2 + 1e8 t + 1 are approximately -1e8 and -1e-8
+        let (a, b, c) = (1.0, 1e8, 1.0);
+        let exact_small = -1e-8;
+
+        let (t0, t1) = solve_quadratic(a, b, c).unwrap();
+        assert!(((t0 + 1e8) / 1e8).abs() < 1e-15);
+        assert!(((t1 - exact_small) / exact_small).abs() < 1e-15);
 
-        assert_eq!(Tuple4{ x: 0.5, y: -1.0, z: 1.5, w: -2.0 }, t / 2.0);
+        // The textbook formula loses most of the significant digits
+        let naive = (-b + (b * b - 4.0 * a * c).sqrt()) / (2.0 * a);
+        assert!(((naive - exact_small) / exact_small).abs() > 1e-3);
     }
 }
@@ -1,3 +1,44 @@
+/// Floating point functions that are provided by `std` but not by `core`.
+///
+/// Bring this trait into scope to use the same method syntax with and
+/// without `std`. Test builds always link `std`, so it's not needed there.
+///
+/// `abs` and `copysign` are only provided by `core` since Rust 1.85.
+/// On newer toolchains the inherent methods take precedence.
+#[cfg(not(any(feature = "std", test)))]
+pub(crate) trait Float {
+    fn sqrt(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn ln(self) -> Self;
+    #[allow(dead_code)]
+    fn abs(self) -> Self;
+    #[allow(dead_code)]
+    fn copysign(self, sign: Self) -> Self;
+}
+
+#[cfg(not(any(feature = "std", test)))]
+impl Float for f64 {
+    fn sqrt(self) -> Self {
+        libm::sqrt(self)
+    }
+
+    fn powf(self, n: Self) -> Self {
+        libm::pow(self, n)
+    }
+
+    fn ln(self) -> Self {
+        libm::log(self)
+    }
+
+    fn abs(self) -> Self {
+        libm::fabs(self)
+    }
+
+    fn copysign(self, sign: Self) -> Self {
+        libm::copysign(self, sign)
+    }
+}
+
 /// Compare two f64 floating point numbers for equality.
 pub fn equal(lhs: f64, rhs: f64) -> bool {
     const EPSILON: f64 = 1e-10;
@@ -0,0 +1,31 @@
+//! Commonly used types and traits.
+//!
+//! Bring everything into scope using `use sray::prelude::*;`.
+#![cfg_attr(feature = "std", doc = r##"
+# Examples
+
+```
+use sray::prelude::*;
+
+let mut canvas = Canvas::new(5, 5);
+let center = Point3::new(2.0, 2.0, 0.0);
+
+// Draw a small cross around the center
+for v in &[Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)] {
+    for p in &[center + *v, center - *v] {
+        canvas.write_pixel(p.x() as usize, p.y() as usize, Color::ORANGE);
+    }
+}
+
+assert_eq!(&Color::ORANGE, canvas.pixel_at(3, 2).unwrap());
+assert_eq!(&Color::ORANGE, canvas.pixel_at(2, 1).unwrap());
+assert_eq!(&Color::BLACK, canvas.pixel_at(2, 2).unwrap());
+```
+"##)]
+
+pub use crate::math::{Point3, Vector3};
+pub use crate::color::{Color, DefaultColors};
+#[cfg(feature = "std")]
+pub use crate::canvas::Canvas;
+#[cfg(feature = "std")]
+pub use crate::camera::CameraPath;
@@ -0,0 +1,463 @@
+//! Sampling utilities.
+//!
+//! Features like ambient occlusion and area lights need sample
+//! directions distributed around a surface normal. The functions of
+//! this module draw their random numbers from a [`Sequence`], so the
+//! same sequence always yields the same directions.
+//!
+//! Sample positions within the unit square (e.g. for anti-aliasing)
+//! are generated using a [`SamplePattern`].
+
+use std::f64::consts::PI;
+use super::math::{basis_from_normal, Vector3};
+
+/// A source of numbers in `[0, 1)` used to place samples.
+pub trait Sequence {
+    /// Returns the next number of the sequence.
+    fn next(&mut self) -> f64;
+}
+
+/// A sequence that cycles through a fixed list of numbers.
+///
+/// # Examples
+///
+/// ```
+/// use sray::sampling::{CyclicSequence, Sequence};
+///
+/// let mut seq = CyclicSequence::new(vec![0.1, 0.5, 0.9]);
+///
+/// assert_eq!(0.1, seq.next());
+/// assert_eq!(0.5, seq.next());
+/// assert_eq!(0.9, seq.next());
+/// assert_eq!(0.1, seq.next());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CyclicSequence {
+    values: Vec<f64>,
+    index: usize,
+}
+
+impl CyclicSequence {
+
+    /// Create a new sequence cycling through `values`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` is empty or if any value lies outside of `[0, 1)`.
+    pub fn new(values: Vec<f64>) -> Self {
+        assert!(!values.is_empty(), "sequence requires at least one value");
+        assert!(values.iter().all(|v| (0.0..1.0).contains(v)), "sequence values must lie within [0, 1)");
+        Self { values, index: 0 }
+    }
+}
+
+impl Sequence for CyclicSequence {
+    fn next(&mut self) -> f64 {
+        let value = self.values[self.index];
+        self.index = (self.index + 1) % self.values.len();
+        value
+    }
+}
+
+/// A pseudo random sequence (SplitMix64).
+///
+/// The same seed always yields the same sequence of numbers.
+///
+/// # Examples
+///
+/// ```
+/// use sray::sampling::{RandomSequence, Sequence};
+///
+/// let mut s1 = RandomSequence::new(42);
+/// let mut s2 = RandomSequence::new(42);
+///
+/// let x = s1.next();
+/// assert!((0.0..1.0).contains(&x));
+/// assert_eq!(x, s2.next());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RandomSequence {
+    state: u64,
+}
+
+impl RandomSequence {
+
+    /// Create a new random sequence using the given `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Sequence for RandomSequence {
+    fn next(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        // Use the upper 53 bits as mantissa of a number in [0, 1)
+        (z >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+/// Calculate the `index`-th element of the Halton sequence for `base`.
+///
+/// The Halton sequence is a low-discrepancy sequence, i.e. its
+/// elements cover `[0, 1)` more evenly than random numbers. The
+/// sequence starts at index 1. Using coprime bases (e.g. 2 and 3) for
+/// each dimension yields well distributed points in the unit square.
+///
+/// # Examples
+///
+/// ```
+/// use sray::sampling::halton;
+///
+/// assert_eq!(0.5, halton(1, 2));
+/// assert_eq!(0.25, halton(2, 2));
+/// assert_eq!(0.75, halton(3, 2));
+/// assert_eq!(1.0 / 3.0, halton(1, 3));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `base` is smaller than 2.
+pub fn halton(index: usize, base: usize) -> f64 {
+    assert!(base >= 2, "halton base must be at least 2");
+
+    let mut index = index;
+    let mut f = 1.0;
+    let mut result = 0.0;
+
+    while index > 0 {
+        f /= base as f64;
+        result += f * (index % base) as f64;
+        index /= base;
+    }
+
+    result
+}
+
+/// A progressive blue noise tile, i.e. every prefix of the tile is
+/// evenly distributed over the unit square (best-candidate sampling).
+const BLUE_NOISE_TILE: [(f64, f64); 64] = [
+    (0.443054, 0.736611), (0.979231, 0.351699), (0.940724, 0.850238), (0.337691, 0.133806),
+    (0.646278, 0.019994), (0.594271, 0.379149), (0.266714, 0.453628), (0.772892, 0.569644),
+    (0.072436, 0.070879), (0.136065, 0.669420), (0.680866, 0.790140), (0.257022, 0.863977),
+    (0.756505, 0.226352), (0.877062, 0.079824), (0.511904, 0.563904), (0.407554, 0.317710),
+    (0.980899, 0.552194), (0.486923, 0.952837), (0.173388, 0.269000), (0.514272, 0.148134),
+    (0.091070, 0.917802), (0.333942, 0.616008), (0.822065, 0.390842), (0.876967, 0.712555),
+    (0.786774, 0.931414), (0.618890, 0.665882), (0.960261, 0.205796), (0.218206, 0.031601),
+    (0.098157, 0.439184), (0.640529, 0.536162), (0.352037, 0.988535), (0.400106, 0.478694),
+    (0.647289, 0.156669), (0.998383, 0.724050), (0.552607, 0.829310), (0.274062, 0.737213),
+    (0.133262, 0.795246), (0.388589, 0.858497), (0.957415, 0.974255), (0.566750, 0.260128),
+    (0.216734, 0.567444), (0.269970, 0.332358), (0.080173, 0.194071), (0.718813, 0.339742),
+    (0.678785, 0.902479), (0.097571, 0.559493), (0.759544, 0.108853), (0.217266, 0.158276),
+    (0.429119, 0.070173), (0.880624, 0.509058), (0.750062, 0.684196), (0.811308, 0.819681),
+    (0.875999, 0.289086), (0.498231, 0.438278), (0.723775, 0.450764), (0.405656, 0.211093),
+    (0.537240, 0.042899), (0.082150, 0.331766), (0.174122, 0.378625), (0.310765, 0.231458),
+    (0.988668, 0.450943), (0.346415, 0.396472), (0.884277, 0.611146), (0.440613, 0.634186),
+];
+
+/// The pattern used to place samples within the unit square, e.g.
+/// within a pixel for anti-aliasing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplePattern {
+    /// Samples at the centers of a regular grid.
+    Grid,
+    /// Pseudo random samples.
+    Random,
+    /// Samples taken from the Halton sequence (bases 2 and 3).
+    Halton,
+    /// Samples taken from a precomputed blue noise tile.
+    BlueNoise,
+}
+
+impl SamplePattern {
+
+    /// Generate `count` sample positions within `[0, 1) x [0, 1)`.
+    ///
+    /// * `Grid` divides the unit square into `count` cells of equal area,
+    ///   arranged in rows of (nearly) the same length, and places a sample
+    ///   at the center of each cell. If `count` is a perfect square this
+    ///   is a regular grid.
+    /// * `Random` draws the samples from a [`RandomSequence`] initialized
+    ///   with `seed`. All other patterns ignore the seed.
+    /// * `BlueNoise` repeats the 64 sample tile with a different offset
+    ///   if more samples are requested.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sray::sampling::SamplePattern;
+    ///
+    /// let samples = SamplePattern::Grid.samples(4, 0);
+    /// assert_eq!(vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)], samples);
+    ///
+    /// let samples = SamplePattern::Halton.samples(2, 0);
+    /// assert_eq!(vec![(0.5, 1.0 / 3.0), (0.25, 2.0 / 3.0)], samples);
+    /// ```
+    pub fn samples(&self, count: usize, seed: u64) -> Vec<(f64, f64)> {
+        match self {
+            SamplePattern::Grid => {
+                let cols = (1..).find(|n| n * n >= count).unwrap_or(1);
+                let rows = if (cols - 1) * cols >= count { cols - 1 } else { cols };
+                let mut samples = Vec::with_capacity(count);
+
+                // The height of each row is proportional to its number of
+                // samples, so every sample gets a cell of the same area
+                for row in 0..rows {
+                    let k = count / rows + usize::from(row < count % rows);
+                    let y = (samples.len() as f64 + k as f64 / 2.0) / count as f64;
+
+                    samples.extend((0..k).map(|col| ((col as f64 + 0.5) / k as f64, y)));
+                }
+
+                samples
+            }
+            SamplePattern::Random => {
+                let mut seq = RandomSequence::new(seed);
+
+                (0..count).map(|_| (seq.next(), seq.next())).collect()
+            }
+            SamplePattern::Halton => {
+                (1..=count).map(|i| (halton(i, 2), halton(i, 3))).collect()
+            }
+            SamplePattern::BlueNoise => {
+                // Offset every repetition of the tile along an irrational direction
+                const OFFSET: (f64, f64) = (0.618_033_988_749_895, 0.754_877_666_246_693);
+
+                (0..count)
+                    .map(|i| {
+                        let (x, y) = BLUE_NOISE_TILE[i % BLUE_NOISE_TILE.len()];
+                        let k = (i / BLUE_NOISE_TILE.len()) as f64;
+
+                        ((x + k * OFFSET.0).fract(), (y + k * OFFSET.1).fract())
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Sample a direction from the hemisphere around `normal` with a
+/// uniform distribution.
+///
+/// Two numbers are drawn from `seq` per sample. `normal` is expected
+/// to be a unit vector. The dot product of every sample with `normal`
+/// is positive, i.e. no sample is tangent to the surface.
+///
+/// # Examples
+///
+/// ```
+/// use sray::math::Vector3;
+/// use sray::sampling::{uniform_hemisphere, CyclicSequence};
+///
+/// let n = Vector3::new(0.0, 1.0, 0.0);
+/// let mut seq = CyclicSequence::new(vec![0.3, 0.7]);
+/// let d = uniform_hemisphere(n, &mut seq);
+///
+/// assert!((d.mag() - 1.0).abs() < 1e-10);
+/// assert!((d.dot(&n) - 0.7).abs() < 1e-10);
+/// ```
+pub fn uniform_hemisphere(normal: Vector3, seq: &mut impl Sequence) -> Vector3 {
+    // Map [0, 1) to (0, 1], so no sample is tangent to the surface
+    let cos_theta = 1.0 - seq.next();
+    let phi = 2.0 * PI * seq.next();
+    let r = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+
+    to_world(normal, r * phi.cos(), r * phi.sin(), cos_theta)
+}
+
+/// Sample a direction from the hemisphere around `normal` with a
+/// cosine-weighted distribution.
+///
+/// Directions close to the normal are more likely than grazing ones,
+/// matching the falloff of diffuse lighting. Two numbers are drawn from
+/// `seq` per sample. `normal` is expected to be a unit vector.
+/// The dot product of every sample with `normal` is positive.
+///
+/// # Examples
+///
+/// ```
+/// use sray::math::Vector3;
+/// use sray::sampling::{cosine_weighted_hemisphere, CyclicSequence};
+///
+/// let n = Vector3::new(0.0, 0.0, 1.0);
+/// let mut seq = CyclicSequence::new(vec![0.36, 0.25]);
+/// let d = cosine_weighted_hemisphere(n, &mut seq);
+///
+/// assert!((d.mag() - 1.0).abs() < 1e-10);
+/// assert!((d.dot(&n) - 0.8).abs() < 1e-10);
+/// ```
+pub fn cosine_weighted_hemisphere(normal: Vector3, seq: &mut impl Sequence) -> Vector3 {
+    let u = seq.next();
+    let phi = 2.0 * PI * seq.next();
+    let r = u.sqrt();
+
+    to_world(normal, r * phi.cos(), r * phi.sin(), (1.0 - u).max(0.0).sqrt())
+}
+
+/// Transform a direction given relative to `normal` (which becomes the
+/// z axis) into world space.
+fn to_world(normal: Vector3, x: f64, y: f64, z: f64) -> Vector3 {
+    let (tangent, bitangent, normal) = basis_from_normal(normal);
+
+    tangent * x + bitangent * y + normal * z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N: usize = 16;
+
+    /// A stratified grid of `N * N` sample pairs.
+    fn grid() -> CyclicSequence {
+        let mut values = Vec::new();
+        for i in 0..N {
+            for j in 0..N {
+                values.push((i as f64 + 0.5) / N as f64);
+                values.push((j as f64 + 0.5) / N as f64);
+            }
+        }
+        CyclicSequence::new(values)
+    }
+
+    fn normals() -> Vec<Vector3> {
+        vec![
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(1.0, -2.0, 3.0).norm(),
+            Vector3::new(1e-9, 0.0, -1.0).norm(),
+        ]
+    }
+
+    #[test]
+    fn samples_lie_in_the_hemisphere_of_the_normal() {
+        for n in normals() {
+            let mut seq = grid();
+            for _ in 0..N * N {
+                let u = uniform_hemisphere(n, &mut seq);
+                let c = cosine_weighted_hemisphere(n, &mut seq);
+
+                assert!(u.dot(&n) > 0.0);
+                assert!(c.dot(&n) > 0.0);
+                assert!((u.mag() - 1.0).abs() < 1e-10);
+                assert!((c.mag() - 1.0).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn edge_values_of_the_sequence_yield_no_tangent_samples() {
+        for n in normals() {
+            let mut seq = CyclicSequence::new(vec![0.0, 0.0]);
+
+            assert!(uniform_hemisphere(n, &mut seq).dot(&n) > 0.0);
+            assert!(cosine_weighted_hemisphere(n, &mut seq).dot(&n) > 0.0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "[0, 1)")]
+    fn cyclic_sequence_rejects_values_outside_of_the_unit_interval() {
+        CyclicSequence::new(vec![0.5, 1.0]);
+    }
+
+    #[test]
+    fn cosine_weighting_biases_samples_toward_the_normal() {
+        for n in normals() {
+            let (mut seq_u, mut seq_c) = (grid(), grid());
+            let mut uniform = 0.0;
+            let mut cosine = 0.0;
+
+            for _ in 0..N * N {
+                uniform += uniform_hemisphere(n, &mut seq_u).dot(&n);
+                cosine += cosine_weighted_hemisphere(n, &mut seq_c).dot(&n);
+            }
+
+            // The expected cosines are 1/2 (uniform) and 2/3 (cosine-weighted)
+            let (uniform, cosine) = (uniform / (N * N) as f64, cosine / (N * N) as f64);
+            assert!((uniform - 0.5).abs() < 1e-2);
+            assert!((cosine - 2.0 / 3.0).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn halton_sequence_produces_known_values() {
+        let base2: Vec<f64> = (1..=7).map(|i| halton(i, 2)).collect();
+        let base3: Vec<f64> = (1..=5).map(|i| halton(i, 3)).collect();
+
+        assert_eq!(vec![0.5, 0.25, 0.75, 0.125, 0.625, 0.375, 0.875], base2);
+        for (expected, actual) in [1.0 / 3.0, 2.0 / 3.0, 1.0 / 9.0, 4.0 / 9.0, 7.0 / 9.0].iter().zip(base3) {
+            assert!((expected - actual).abs() < 1e-12);
+        }
+        assert_eq!(0.0, halton(0, 2));
+    }
+
+    #[test]
+    fn all_patterns_yield_samples_within_the_unit_square() {
+        let patterns = [SamplePattern::Grid, SamplePattern::Random, SamplePattern::Halton, SamplePattern::BlueNoise];
+
+        for pattern in &patterns {
+            for count in &[0, 1, 5, 16, 64, 200] {
+                let samples = pattern.samples(*count, 7);
+
+                assert_eq!(*count, samples.len());
+                assert!(samples.iter().all(|(x, y)| (0.0..1.0).contains(x) && (0.0..1.0).contains(y)));
+            }
+        }
+    }
+
+    #[test]
+    fn grid_pattern_is_centered_for_any_count() {
+        for count in 1..=20 {
+            let samples = SamplePattern::Grid.samples(count, 0);
+            let mean_x = samples.iter().map(|s| s.0).sum::<f64>() / count as f64;
+            let mean_y = samples.iter().map(|s| s.1).sum::<f64>() / count as f64;
+
+            assert!((mean_x - 0.5).abs() < 1e-10, "count {}: mean x {}", count, mean_x);
+            assert!((mean_y - 0.5).abs() < 1e-10, "count {}: mean y {}", count, mean_y);
+        }
+
+        assert_eq!(vec![(0.25, 0.5), (0.75, 0.5)], SamplePattern::Grid.samples(2, 0));
+    }
+
+    #[test]
+    fn random_pattern_depends_on_seed_only() {
+        let s1 = SamplePattern::Random.samples(8, 1);
+
+        assert_eq!(s1, SamplePattern::Random.samples(8, 1));
+        assert_ne!(s1, SamplePattern::Random.samples(8, 2));
+        assert_eq!(SamplePattern::Halton.samples(8, 1), SamplePattern::Halton.samples(8, 2));
+    }
+
+    #[test]
+    fn halton_and_blue_noise_are_more_even_than_random() {
+        // Approximate the star discrepancy, i.e. the largest difference between
+        // the fraction of samples within [0, a) x [0, b) and the area a * b.
+        let discrepancy = |samples: Vec<(f64, f64)>| {
+            let mut max: f64 = 0.0;
+            for i in 1..=32 {
+                for j in 1..=32 {
+                    let (a, b) = (i as f64 / 32.0, j as f64 / 32.0);
+                    let inside = samples.iter().filter(|(x, y)| *x < a && *y < b).count();
+                    max = max.max((inside as f64 / samples.len() as f64 - a * b).abs());
+                }
+            }
+            max
+        };
+
+        for count in &[16, 64] {
+            let random = (0..32)
+                .map(|seed| discrepancy(SamplePattern::Random.samples(*count, seed)))
+                .sum::<f64>() / 32.0;
+
+            assert!(discrepancy(SamplePattern::Halton.samples(*count, 0)) < random);
+            assert!(discrepancy(SamplePattern::BlueNoise.samples(*count, 0)) < random);
+        }
+    }
+}
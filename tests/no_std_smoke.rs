@@ -0,0 +1,39 @@
+//! Smoke test for the `math` and `color` modules.
+//!
+//! Integration tests link the library as a regular dependency, so running
+//! `cargo test --no-default-features --features libm --test no_std_smoke`
+//! exercises the `#![no_std]` build backed by `libm`.
+
+use sray::color::{Color, DefaultColors};
+use sray::math::{Point3, Vector3};
+
+#[test]
+fn point_and_vector_arithmetic() {
+    let p = Point3::new(3.0, -2.0, 5.0) + Vector3::new(-2.0, 3.0, 1.0);
+    assert_eq!(Point3::new(1.0, 1.0, 6.0), p);
+
+    let v = Point3::new(3.0, 2.0, 1.0) - Point3::new(5.0, 6.0, 7.0);
+    assert_eq!(Vector3::new(-2.0, -4.0, -6.0), v);
+
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    assert_eq!(14.0_f64.sqrt(), v.mag());
+    assert_eq!(1.0, v.norm().mag());
+    assert_eq!(20.0, v.dot(&Vector3::new(2.0, 3.0, 4.0)));
+    assert_eq!(Vector3::new(-1.0, 2.0, -1.0), v.cross(&Vector3::new(2.0, 3.0, 4.0)));
+    assert_eq!(Vector3::new(-0.5, -1.0, -1.5), -v / 2.0);
+}
+
+#[test]
+fn color_arithmetic() {
+    let c1 = Color::new(0.9, 0.6, 0.75);
+    let c2 = Color::new(0.7, 0.1, 0.25);
+
+    assert_eq!(Color::new(1.6, 0.7, 1.0), c1.clone() + c2.clone());
+    assert_eq!(Color::new(0.2, 0.5, 0.5), c1.clone() - c2.clone());
+    assert_eq!(Color::new(0.63, 0.06, 0.1875), c1 * c2);
+    assert_eq!(Color::new(2.0, 0.0, 1.0), Color::MAGENTA * 2.0 - Color::BLUE);
+
+    let c = Color::new(0.5, 0.5, 0.5).from_srgb();
+    assert!((c.r() - 0.214041).abs() < 1e-6);
+    assert_eq!(1.0, Color::from_kelvin(2700.0).r());
+}